@@ -1,6 +1,7 @@
 use super::*;
 use std::fmt::{self, Debug, Formatter};
 
+#[derive(Clone)]
 pub struct Block {
     pub index: u32,
     pub timestamp: u128,
@@ -8,7 +9,11 @@ pub struct Block {
     pub previous_block_hash: Hash,
     pub nonce: u64,
     pub transactions: Vec<Transaction>,
+    pub merkle_root: Hash,
     pub difficulty: u128,
+    /// The validator that proposed this block under proof-of-stake.
+    /// `None` for proof-of-work blocks, which have no proposer to check.
+    pub proposer: Option<String>,
 }
 
 impl Debug for Block {
@@ -33,6 +38,7 @@ impl Block {
         transactions: Vec<Transaction>,
         difficulty: u128,
     ) -> Self {
+        let merkle_root = crate::merkle::merkle_root(&transactions);
         Block {
             index,
             timestamp,
@@ -40,10 +46,19 @@ impl Block {
             previous_block_hash,
             nonce: 0,
             transactions,
+            merkle_root,
             difficulty,
+            proposer: None,
         }
     }
 
+    /// This block's difficulty target in compact ("nBits") form, suitable
+    /// for storing or transmitting alongside the block header instead of
+    /// the full 128-bit target.
+    pub fn bits(&self) -> u32 {
+        u32::from_be_bytes(crate::compact::Compact::from_u128(self.difficulty).bytes())
+    }
+
     pub fn mine(&mut self) {
         for none_attempt in 0..(u64::max_value()) {
             self.nonce = none_attempt;
@@ -63,17 +78,63 @@ impl Hashable for Block {
         bytes.extend(&u128_bytes(&self.timestamp));
         bytes.extend(&self.previous_block_hash);
         bytes.extend(&u64_bytes(&self.nonce));
-        bytes.extend(
-            self.transactions
-                .iter()
-                .flat_map(|tran| tran.bytes())
-                .collect::<Vec<u8>>(),
-        );
-        bytes.extend(&u128_bytes(&self.difficulty));
+        // The header commits to the transaction set via `merkle_root` alone,
+        // not the full transaction bytes, so the block hash stays small and
+        // independent of how many transactions the block carries — the
+        // prerequisite for SPV-style light clients that never download the
+        // transactions themselves.
+        bytes.extend(&self.merkle_root);
+        bytes.extend(&crate::compact::Compact::from_u128(self.difficulty).bytes());
+        if let Some(proposer) = &self.proposer {
+            bytes.extend(proposer.as_bytes());
+        }
         bytes
     }
 }
 
+/// A block clears the difficulty check if its hash falls under the target
+/// its header actually committed to. Headers only commit the *compact*
+/// encoding of the target (see `Hashable::bytes` above), so `difficulty` is
+/// rounded through `Compact` here too before comparing — otherwise a block
+/// could be mined against a full-precision target that doesn't match what
+/// a light client, which only ever sees the compact bytes, would check.
 pub fn check_difficulty(hash: &Hash, difficulty: u128) -> bool {
-    difficulty > difficulty_bytes_as_u128(&hash)
+    let target = crate::compact::Compact::from_u128(difficulty).to_u128();
+    target > difficulty_bytes_as_u128(&hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(value: u64) -> Transaction {
+        Transaction {
+            inputs: vec![],
+            outputs: vec![transaction::Output {
+                to_address: "Alice".to_owned(),
+                value,
+            }],
+        }
+    }
+
+    #[test]
+    fn header_bytes_do_not_grow_with_transaction_count() {
+        let one = Block::new(0, 0, vec![0; 32], vec![transaction(1)], 1_000);
+        let many = Block::new(
+            0,
+            0,
+            vec![0; 32],
+            vec![transaction(1), transaction(2), transaction(3)],
+            1_000,
+        );
+        assert_eq!(one.bytes().len(), many.bytes().len());
+    }
+
+    #[test]
+    fn header_hash_changes_when_transactions_change() {
+        let a = Block::new(0, 0, vec![0; 32], vec![transaction(1)], 1_000);
+        let b = Block::new(0, 0, vec![0; 32], vec![transaction(2)], 1_000);
+        assert_ne!(a.merkle_root, b.merkle_root);
+        assert_ne!(a.bytes(), b.bytes());
+    }
 }
@@ -1,5 +1,8 @@
 use crate::block::{check_difficulty, Block};
 use crate::hashable::Hashable;
+use crate::mempool::Mempool;
+use crate::store::Store;
+use crate::transaction::{Output, Transaction};
 use crate::Hash;
 use std::collections::HashSet;
 
@@ -13,8 +16,21 @@ pub enum BlockValidationError {
     InvalidInput,
     InsufficientInputValue,
     InvalidCoinbaseTransaction,
+    InvalidDifficulty,
+    WrongProposer,
+    InsufficientStake,
 }
 
+/// Number of blocks between difficulty retargets.
+pub const RETARGET_INTERVAL: usize = 10;
+/// Desired average time between blocks, in the same units as `Block::timestamp`.
+pub const TARGET_BLOCK_TIME_MILLIS: u128 = 30_000;
+/// Difficulty the genesis block is expected to be mined at.
+pub const INITIAL_DIFFICULTY: u128 = 0x000fffffffffffffffffffffffffffff;
+/// Coinbase reward paid to a block's miner/proposer, on top of the fees
+/// collected from the transactions it includes.
+pub const BLOCK_SUBSIDY: u64 = 50;
+
 pub struct BlockChain {
     pub blocks: Vec<Block>,
     unspent_outputs: HashSet<Hash>,
@@ -28,11 +44,92 @@ impl BlockChain {
         }
     }
 
+    /// The set of transaction output hashes this chain considers spendable,
+    /// as of its current tip. Used by things like the mempool's block
+    /// assembler to tell which candidate transactions can actually be spent.
+    pub fn unspent_outputs(&self) -> &HashSet<Hash> {
+        &self.unspent_outputs
+    }
+
+    /// Builds an unmined block template extending this chain: a coinbase
+    /// transaction paying `coinbase_address` the block subsidy plus the
+    /// fees of whichever mempool transactions were selected, at the index,
+    /// previous hash, and difficulty this chain expects next. The caller
+    /// still has to seal it (e.g. `Block::mine` or a `Consensus` engine)
+    /// before it's valid to extend the chain with.
+    pub fn assemble_block(
+        &self,
+        mempool: &Mempool,
+        coinbase_address: &str,
+        timestamp: u128,
+        max_transactions: usize,
+    ) -> Block {
+        let selected = mempool.assemble_transactions(&self.unspent_outputs, max_transactions);
+        let total_fee: u64 = selected
+            .iter()
+            .map(|transaction| transaction.input_value() - transaction.output_value())
+            .sum();
+
+        let coinbase = Transaction {
+            inputs: vec![],
+            outputs: vec![Output {
+                to_address: coinbase_address.to_owned(),
+                value: BLOCK_SUBSIDY + total_fee,
+            }],
+        };
+
+        let mut transactions = vec![coinbase];
+        transactions.extend(selected);
+
+        let previous_block_hash = self
+            .blocks
+            .last()
+            .map(|block| block.hash.clone())
+            .unwrap_or_else(|| vec![0; 32]);
+
+        Block::new(
+            self.blocks.len() as u32,
+            timestamp,
+            previous_block_hash,
+            transactions,
+            self.next_difficulty(),
+        )
+    }
+
+    /// Returns the difficulty the next block must be mined at. Every
+    /// `RETARGET_INTERVAL` blocks this compares the time the last interval
+    /// actually took against `TARGET_BLOCK_TIME_MILLIS` and scales the
+    /// difficulty accordingly, clamped to a 4x swing in either direction so
+    /// a handful of outlier timestamps can't retarget the chain too wildly.
+    pub fn next_difficulty(&self) -> u128 {
+        let len = self.blocks.len();
+        if len == 0 {
+            return INITIAL_DIFFICULTY;
+        }
+
+        let last_difficulty = self.blocks[len - 1].difficulty;
+        if len % RETARGET_INTERVAL != 0 {
+            return last_difficulty;
+        }
+
+        let period_start = &self.blocks[len - RETARGET_INTERVAL];
+        let period_end = &self.blocks[len - 1];
+        let actual_timespan = period_end.timestamp - period_start.timestamp;
+        let expected_timespan = TARGET_BLOCK_TIME_MILLIS * (RETARGET_INTERVAL - 1) as u128;
+
+        let adjusted = last_difficulty.saturating_mul(actual_timespan) / expected_timespan;
+        adjusted
+            .max(last_difficulty / 4)
+            .min(last_difficulty.saturating_mul(4))
+    }
+
     pub fn update_with_block(&mut self, block: Block) -> Result<(), BlockValidationError> {
         let i = self.blocks.len();
 
         if block.index != i as u32 {
             return Err(BlockValidationError::MismatchedIndex);
+        } else if block.difficulty != self.next_difficulty() {
+            return Err(BlockValidationError::InvalidDifficulty);
         } else if !check_difficulty(&block.hash(), block.difficulty) {
             return Err(BlockValidationError::InvalidHash);
         } else if i != 0 {
@@ -93,4 +190,124 @@ impl BlockChain {
         self.blocks.push(block);
         Ok(())
     }
+
+    /// Rebuilds a chain by replaying every block out of `store`, from
+    /// genesis up to its current height, through the same validation
+    /// `update_with_block` runs for freshly-mined blocks. This is how a
+    /// node restores its UTXO set on startup instead of trusting whatever
+    /// snapshot was last written to disk.
+    pub fn from_store<S: Store>(store: &S) -> Result<Self, PersistError<S::Error>> {
+        let mut chain = BlockChain::new();
+        for index in 0..store.height() {
+            let block = store
+                .block_by_index(index)
+                .map_err(PersistError::Store)?
+                .expect("store reported this index in its height");
+            chain
+                .update_with_block(block.clone())
+                .map_err(PersistError::Validation)?;
+        }
+        Ok(chain)
+    }
+
+    /// Persists this chain's current tip and UTXO snapshot into `store`.
+    /// Call this after a successful `update_with_block` to keep the store
+    /// caught up.
+    pub fn persist<S: Store>(&self, store: &mut S) -> Result<(), S::Error> {
+        if let Some(block) = self.blocks.last() {
+            store.insert_block(block.clone(), &self.unspent_outputs)?;
+        }
+        Ok(())
+    }
+}
+
+/// Either the store failed to read/write, or a stored block failed the
+/// same validation a freshly-mined block would.
+#[derive(Debug)]
+pub enum PersistError<E> {
+    Store(E),
+    Validation(BlockValidationError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_at(index: u32, timestamp: u128, difficulty: u128) -> Block {
+        Block::new(index, timestamp, vec![0; 32], vec![], difficulty)
+    }
+
+    fn chain_with_blocks(timestamps: &[u128], difficulty: u128) -> BlockChain {
+        let mut chain = BlockChain::new();
+        for (index, &timestamp) in timestamps.iter().enumerate() {
+            chain.blocks.push(block_at(index as u32, timestamp, difficulty));
+        }
+        chain
+    }
+
+    #[test]
+    fn genesis_uses_the_initial_difficulty() {
+        assert_eq!(BlockChain::new().next_difficulty(), INITIAL_DIFFICULTY);
+    }
+
+    #[test]
+    fn holds_steady_before_a_retarget_interval() {
+        let chain = chain_with_blocks(&[0, 30_000, 60_000], 1_000);
+        assert_eq!(chain.next_difficulty(), 1_000);
+    }
+
+    #[test]
+    fn loosens_when_blocks_arrived_slower_than_target() {
+        let timestamps: Vec<u128> = (0..RETARGET_INTERVAL as u128)
+            .map(|i| i * TARGET_BLOCK_TIME_MILLIS * 2)
+            .collect();
+        let chain = chain_with_blocks(&timestamps, 1_000);
+        assert!(chain.next_difficulty() > 1_000);
+    }
+
+    #[test]
+    fn tightens_when_blocks_arrived_faster_than_target() {
+        let timestamps: Vec<u128> = (0..RETARGET_INTERVAL as u128)
+            .map(|i| i * TARGET_BLOCK_TIME_MILLIS / 2)
+            .collect();
+        let chain = chain_with_blocks(&timestamps, 1_000);
+        assert!(chain.next_difficulty() < 1_000);
+    }
+
+    #[test]
+    fn clamps_extreme_swings_to_four_x() {
+        let mut timestamps: Vec<u128> = vec![0; RETARGET_INTERVAL];
+        timestamps[RETARGET_INTERVAL - 1] = TARGET_BLOCK_TIME_MILLIS * 1_000;
+        let chain = chain_with_blocks(&timestamps, 1_000);
+        assert_eq!(chain.next_difficulty(), 4_000);
+    }
+
+    #[test]
+    fn persists_and_replays_a_chain_through_a_store() {
+        use crate::store::InMemoryStore;
+
+        let mut chain = BlockChain::new();
+        let mut genesis = Block::new(
+            0,
+            0,
+            vec![0; 32],
+            vec![Transaction {
+                inputs: vec![],
+                outputs: vec![Output {
+                    to_address: "Alice".to_owned(),
+                    value: 50,
+                }],
+            }],
+            chain.next_difficulty(),
+        );
+        genesis.mine();
+        chain.update_with_block(genesis).unwrap();
+
+        let mut store = InMemoryStore::new();
+        chain.persist(&mut store).unwrap();
+
+        let replayed = BlockChain::from_store(&store).unwrap();
+        assert_eq!(replayed.blocks.len(), 1);
+        assert_eq!(replayed.unspent_outputs(), chain.unspent_outputs());
+    }
 }
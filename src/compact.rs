@@ -0,0 +1,91 @@
+/// Encode a 128-bit difficulty target into a compact ("nBits") form: a
+/// one-byte exponent (the target's length in bytes) followed by a
+/// three-byte mantissa (its most significant bytes). Mirrors the encoding
+/// Bitcoin headers use so a target can be carried in 4 bytes instead of 16.
+pub fn target_to_bits(target: u128) -> u32 {
+    let bytes = target.to_be_bytes();
+    let exponent = match bytes.iter().position(|&b| b != 0) {
+        Some(idx) => (bytes.len() - idx) as u32,
+        None => return 0,
+    };
+
+    let start = bytes.len() - exponent as usize;
+    let mut mantissa: u32 = 0;
+    for i in 0..3 {
+        let byte = bytes.get(start + i).copied().unwrap_or(0);
+        mantissa = (mantissa << 8) | byte as u32;
+    }
+
+    // If the mantissa's high bit is set it would be read back as negative,
+    // so shift it down a byte and bump the exponent to compensate.
+    if mantissa & 0x0080_0000 != 0 {
+        ((exponent + 1) << 24) | (mantissa >> 8)
+    } else {
+        (exponent << 24) | mantissa
+    }
+}
+
+/// Decode a compact ("nBits") value back into a 128-bit difficulty target.
+pub fn bits_to_target(bits: u32) -> u128 {
+    let exponent = bits >> 24;
+    let mantissa = (bits & 0x00ff_ffff) as u128;
+
+    if exponent <= 3 {
+        mantissa >> (8 * (3 - exponent))
+    } else {
+        mantissa << (8 * (exponent - 3))
+    }
+}
+
+/// A 128-bit difficulty target carried around in its compact ("nBits")
+/// form. Blocks commit to and check against `to_u128()`'s *rounded* value
+/// rather than the original full-precision target, so the difficulty a
+/// block is actually mined and validated against is exactly the difficulty
+/// a light client can recompute from the 4 compact bytes in its header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compact(u32);
+
+impl Compact {
+    pub fn from_u128(target: u128) -> Self {
+        Compact(target_to_bits(target))
+    }
+
+    pub fn to_u128(self) -> u128 {
+        bits_to_target(self.0)
+    }
+
+    pub fn bytes(self) -> [u8; 4] {
+        self.0.to_be_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_small_targets() {
+        for target in [0u128, 1, 255, 256, 0x7f_ffff] {
+            assert_eq!(Compact::from_u128(target).to_u128(), target);
+        }
+    }
+
+    #[test]
+    fn round_trips_main_rs_genesis_difficulty() {
+        let target = 0x000fffffffffffffffffffffffffffffu128;
+        let rounded = Compact::from_u128(target).to_u128();
+        // Compact encoding only keeps the three most-significant mantissa
+        // bytes, so this loses precision but must stay a close, smaller
+        // approximation of the original target.
+        assert!(rounded <= target);
+        assert!(rounded > target / 2);
+    }
+
+    #[test]
+    fn handles_a_mantissa_with_its_high_bit_set() {
+        let target = 0x80u128 << (8 * 13);
+        let bits = Compact::from_u128(target).0;
+        assert_eq!(bits >> 24, 15); // bumped up one from the raw 14-byte length
+        assert_eq!(Compact::from_u128(target).to_u128(), target);
+    }
+}
@@ -0,0 +1,193 @@
+use crate::block::{check_difficulty, Block};
+use crate::blockchain::{BlockChain, BlockValidationError};
+use crate::hashable::Hashable;
+use std::collections::HashMap;
+
+/// A pluggable block-sealing rule: how a block is prepared so that it's
+/// accepted, and how that acceptance is checked against the chain it would
+/// extend. `ProofOfWork` is the original grind-the-nonce rule `Block::mine`
+/// already implements; `ProofOfStake` is an alternative engine with its own
+/// `seal`/`validate_seal`. `BlockChain::update_with_block` does not go
+/// through this trait: it checks the proof-of-work difficulty on every
+/// block, whichever engine sealed it.
+pub trait Consensus {
+    /// Seals `block` in place. For `ProofOfWork` this alone makes
+    /// `validate_seal` accept it; for `ProofOfStake` the block's `proposer`
+    /// must already be the validator elected for its slot.
+    fn seal(&self, block: &mut Block);
+
+    /// Returns `Ok(())` if `block` satisfies this engine's sealing
+    /// requirement to extend `chain`, or the specific reason it doesn't.
+    fn validate_seal(&self, chain: &BlockChain, block: &Block) -> Result<(), BlockValidationError>;
+}
+
+/// The chain's original consensus rule: a block is sealed by grinding its
+/// nonce until the block's hash clears the difficulty target.
+pub struct ProofOfWork;
+
+impl Consensus for ProofOfWork {
+    fn seal(&self, block: &mut Block) {
+        block.mine();
+    }
+
+    fn validate_seal(&self, _chain: &BlockChain, block: &Block) -> Result<(), BlockValidationError> {
+        if check_difficulty(&block.hash(), block.difficulty) {
+            Ok(())
+        } else {
+            Err(BlockValidationError::InvalidHash)
+        }
+    }
+}
+
+/// How often a new proposer is elected, in the same units as
+/// `Block::timestamp`. Every validator checking the same chain tip and the
+/// same slot arrives at the same elected proposer.
+pub const SLOT_MILLIS: u128 = 10_000;
+
+/// A stake-weighted alternative to `ProofOfWork`: instead of grinding a
+/// nonce, each time slot deterministically elects one validator — weighted
+/// by its share of the total staked amount — to propose the block, by
+/// hashing the chain's tip hash together with the slot number into a point
+/// in `[0, total_stake)` and walking the stake table until that point falls
+/// in a validator's range. `validate_seal` re-derives that same election
+/// and checks the block's declared `proposer` against it.
+#[derive(Default)]
+pub struct ProofOfStake {
+    stakes: HashMap<String, u128>,
+}
+
+impl ProofOfStake {
+    pub fn new() -> Self {
+        ProofOfStake {
+            stakes: HashMap::new(),
+        }
+    }
+
+    pub fn set_stake(&mut self, validator: impl Into<String>, amount: u128) {
+        self.stakes.insert(validator.into(), amount);
+    }
+
+    pub fn stake_of(&self, validator: &str) -> u128 {
+        self.stakes.get(validator).copied().unwrap_or(0)
+    }
+
+    fn total_stake(&self) -> u128 {
+        self.stakes.values().sum()
+    }
+
+    /// Elects the validator that should propose the block extending `chain`
+    /// at `timestamp_slot`. Returns `None` if no validator is staked.
+    pub fn elect_proposer(&self, chain: &BlockChain, timestamp_slot: u128) -> Option<&str> {
+        let total = self.total_stake();
+        if total == 0 {
+            return None;
+        }
+
+        let tip_hash = chain
+            .blocks
+            .last()
+            .map(|block| block.hash.clone())
+            .unwrap_or_else(|| vec![0; 32]);
+
+        let mut seed = tip_hash;
+        seed.extend(&timestamp_slot.to_be_bytes());
+        let digest = crypto_hash::digest(crypto_hash::Algorithm::SHA256, &seed);
+        let mut point_bytes = [0u8; 16];
+        point_bytes.copy_from_slice(&digest[0..16]);
+        let point = u128::from_be_bytes(point_bytes) % total;
+
+        let mut validators: Vec<&String> = self.stakes.keys().collect();
+        validators.sort();
+
+        let mut running = 0u128;
+        for validator in validators {
+            running += self.stakes[validator];
+            if point < running {
+                return Some(validator);
+            }
+        }
+        None
+    }
+}
+
+impl Consensus for ProofOfStake {
+    fn seal(&self, block: &mut Block) {
+        // There is no puzzle to grind under proof-of-stake: this re-hashes
+        // once, committing to whatever `proposer` the block already carries.
+        // It does not set `proposer` itself.
+        block.hash = block.hash();
+    }
+
+    fn validate_seal(&self, chain: &BlockChain, block: &Block) -> Result<(), BlockValidationError> {
+        let proposer = block
+            .proposer
+            .as_deref()
+            .ok_or(BlockValidationError::WrongProposer)?;
+
+        if self.stake_of(proposer) == 0 {
+            return Err(BlockValidationError::InsufficientStake);
+        }
+
+        let timestamp_slot = block.timestamp / SLOT_MILLIS;
+        match self.elect_proposer(chain, timestamp_slot) {
+            Some(elected) if elected == proposer => Ok(()),
+            _ => Err(BlockValidationError::WrongProposer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elects_nobody_with_no_stake() {
+        let engine = ProofOfStake::new();
+        let chain = BlockChain::new();
+        assert_eq!(engine.elect_proposer(&chain, 0), None);
+    }
+
+    #[test]
+    fn sole_validator_is_always_elected() {
+        let mut engine = ProofOfStake::new();
+        engine.set_stake("alice", 100);
+        let chain = BlockChain::new();
+        for slot in 0..5 {
+            assert_eq!(engine.elect_proposer(&chain, slot), Some("alice"));
+        }
+    }
+
+    #[test]
+    fn validate_seal_rejects_a_block_with_no_proposer() {
+        let mut engine = ProofOfStake::new();
+        engine.set_stake("alice", 100);
+        let chain = BlockChain::new();
+        let block = Block::new(0, 0, vec![0; 32], vec![], 1_000);
+        assert!(matches!(
+            engine.validate_seal(&chain, &block),
+            Err(BlockValidationError::WrongProposer)
+        ));
+    }
+
+    #[test]
+    fn validate_seal_rejects_an_unstaked_proposer() {
+        let engine = ProofOfStake::new();
+        let chain = BlockChain::new();
+        let mut block = Block::new(0, 0, vec![0; 32], vec![], 1_000);
+        block.proposer = Some("mallory".to_owned());
+        assert!(matches!(
+            engine.validate_seal(&chain, &block),
+            Err(BlockValidationError::InsufficientStake)
+        ));
+    }
+
+    #[test]
+    fn validate_seal_accepts_the_elected_sole_validator() {
+        let mut engine = ProofOfStake::new();
+        engine.set_stake("alice", 100);
+        let chain = BlockChain::new();
+        let mut block = Block::new(0, 0, vec![0; 32], vec![], 1_000);
+        block.proposer = Some("alice".to_owned());
+        assert!(engine.validate_seal(&chain, &block).is_ok());
+    }
+}
@@ -0,0 +1,149 @@
+use super::*;
+use std::collections::HashSet;
+
+/// Holds transactions that have been broadcast but not yet included in a
+/// block, and assembles them into the next block's transaction list,
+/// preferring whichever pay the highest fee.
+#[derive(Default)]
+pub struct Mempool {
+    transactions: Vec<Transaction>,
+}
+
+impl Mempool {
+    pub fn new() -> Self {
+        Mempool {
+            transactions: vec![],
+        }
+    }
+
+    pub fn add_transaction(&mut self, transaction: Transaction) {
+        self.transactions.push(transaction);
+    }
+
+    /// Picks up to `max_transactions` from the pool to go into the next
+    /// block, highest fee first, skipping any transaction whose inputs
+    /// aren't in `unspent_outputs` or that double-spends an input already
+    /// claimed by a higher-fee transaction selected earlier.
+    pub fn assemble_transactions(
+        &self,
+        unspent_outputs: &HashSet<Hash>,
+        max_transactions: usize,
+    ) -> Vec<Transaction> {
+        let mut candidates: Vec<&Transaction> = self
+            .transactions
+            .iter()
+            .filter(|transaction| transaction.output_value() <= transaction.input_value())
+            .collect();
+        candidates.sort_by_key(|transaction| std::cmp::Reverse(fee(transaction)));
+
+        let mut selected = vec![];
+        let mut spent: HashSet<Hash> = HashSet::new();
+        for transaction in candidates {
+            if selected.len() >= max_transactions {
+                break;
+            }
+
+            let input_hashes = transaction.input_hashes();
+            if (&input_hashes - unspent_outputs).is_empty() && (&input_hashes & &spent).is_empty()
+            {
+                spent.extend(input_hashes);
+                selected.push(transaction.clone());
+            }
+        }
+        selected
+    }
+}
+
+fn fee(transaction: &Transaction) -> u64 {
+    transaction.input_value() - transaction.output_value()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(value: u64) -> transaction::Output {
+        transaction::Output {
+            to_address: "Alice".to_owned(),
+            value,
+        }
+    }
+
+    /// A coinbase-style transaction with no inputs whose output the tests
+    /// add to `unspent_outputs` themselves, so they don't need a real
+    /// `BlockChain` to get a valid unspent output to spend from.
+    fn funding_transaction(value: u64) -> Transaction {
+        Transaction {
+            inputs: vec![],
+            outputs: vec![output(value)],
+        }
+    }
+
+    fn spend(funding: &Transaction, fee: u64) -> Transaction {
+        let input = funding.outputs[0].clone();
+        let output_value = input.value - fee;
+        Transaction {
+            inputs: vec![input],
+            outputs: vec![output(output_value)],
+        }
+    }
+
+    #[test]
+    fn selects_transactions_highest_fee_first() {
+        let funding_a = funding_transaction(100);
+        let funding_b = funding_transaction(200);
+        let funding_c = funding_transaction(300);
+
+        let low_fee = spend(&funding_a, 1);
+        let high_fee = spend(&funding_b, 10);
+        let mid_fee = spend(&funding_c, 5);
+
+        let mut unspent_outputs = HashSet::new();
+        unspent_outputs.extend(funding_a.output_hashes());
+        unspent_outputs.extend(funding_b.output_hashes());
+        unspent_outputs.extend(funding_c.output_hashes());
+
+        let mut mempool = Mempool::new();
+        mempool.add_transaction(low_fee.clone());
+        mempool.add_transaction(high_fee.clone());
+        mempool.add_transaction(mid_fee.clone());
+
+        let selected = mempool.assemble_transactions(&unspent_outputs, 10);
+
+        assert_eq!(selected.len(), 3);
+        assert_eq!(fee(&selected[0]), 10);
+        assert_eq!(fee(&selected[1]), 5);
+        assert_eq!(fee(&selected[2]), 1);
+    }
+
+    #[test]
+    fn respects_max_transactions() {
+        let funding_a = funding_transaction(100);
+        let funding_b = funding_transaction(200);
+
+        let mut unspent_outputs = HashSet::new();
+        unspent_outputs.extend(funding_a.output_hashes());
+        unspent_outputs.extend(funding_b.output_hashes());
+
+        let mut mempool = Mempool::new();
+        mempool.add_transaction(spend(&funding_a, 1));
+        mempool.add_transaction(spend(&funding_b, 10));
+
+        let selected = mempool.assemble_transactions(&unspent_outputs, 1);
+
+        assert_eq!(selected.len(), 1);
+        assert_eq!(fee(&selected[0]), 10);
+    }
+
+    #[test]
+    fn skips_transactions_whose_inputs_are_already_spent() {
+        let funding = funding_transaction(100);
+        let unspent_outputs: HashSet<Hash> = HashSet::new(); // funding's output was never added
+
+        let mut mempool = Mempool::new();
+        mempool.add_transaction(spend(&funding, 1));
+
+        let selected = mempool.assemble_transactions(&unspent_outputs, 10);
+        assert!(selected.is_empty());
+    }
+}
@@ -0,0 +1,169 @@
+use super::*;
+
+/// Computes the Merkle root of a list of hashable items, pairing leaves
+/// bottom-up and duplicating the last element of any odd layer (matching
+/// Bitcoin's convention) until a single root hash remains. Returns the
+/// zero hash for an empty list.
+pub fn merkle_root<T: Hashable>(items: &[T]) -> Hash {
+    let leaves: Vec<Hash> = items.iter().map(|item| item.hash()).collect();
+    match merkle_layers(leaves).pop() {
+        Some(mut root_layer) => root_layer.pop().unwrap(),
+        None => vec![0; 32],
+    }
+}
+
+/// Builds every layer of the Merkle tree, from the leaves up to the root,
+/// so callers needing more than just the root (e.g. inclusion proofs) don't
+/// have to recompute the lower layers themselves.
+fn merkle_layers(leaves: Vec<Hash>) -> Vec<Vec<Hash>> {
+    if leaves.is_empty() {
+        return vec![];
+    }
+
+    let mut layers = vec![leaves];
+    while layers.last().unwrap().len() > 1 {
+        let previous = layers.last().unwrap();
+        let mut layer = previous.clone();
+        if layer.len() % 2 == 1 {
+            layer.push(layer.last().unwrap().clone());
+        }
+
+        let next = layer
+            .chunks(2)
+            .map(|pair| {
+                let mut bytes = pair[0].clone();
+                bytes.extend(&pair[1]);
+                crypto_hash::digest(crypto_hash::Algorithm::SHA256, &bytes)
+            })
+            .collect();
+        layers.push(next);
+    }
+    layers
+}
+
+/// A sibling hash on the path from a leaf to the Merkle root, along with
+/// which side of the pair it sits on so the root can be recomputed in order.
+#[derive(Debug, Clone)]
+pub struct MerkleSibling {
+    pub hash: Hash,
+    pub on_right: bool,
+}
+
+/// An SPV-style inclusion proof: the path of sibling hashes needed to walk
+/// a single leaf back up to the Merkle root without holding the rest of
+/// the transactions.
+#[derive(Debug, Clone)]
+pub struct MerkleProof {
+    pub leaf_index: usize,
+    pub siblings: Vec<MerkleSibling>,
+}
+
+/// Builds an inclusion proof for the leaf at `leaf_index`. Returns `None`
+/// if the index is out of range.
+pub fn merkle_proof<T: Hashable>(items: &[T], leaf_index: usize) -> Option<MerkleProof> {
+    if leaf_index >= items.len() {
+        return None;
+    }
+
+    let leaves: Vec<Hash> = items.iter().map(|item| item.hash()).collect();
+    let layers = merkle_layers(leaves);
+
+    let mut siblings = vec![];
+    let mut index = leaf_index;
+    for layer in &layers[..layers.len() - 1] {
+        // `merkle_layers` stores each layer without its duplicated last
+        // node, so the last node of an odd-length layer has no distinct
+        // sibling in the stored layer — it was paired with a clone of itself when the
+        // layer above it was built, so its sibling is itself.
+        let sibling_index = if index % 2 == 0 {
+            if index + 1 < layer.len() {
+                index + 1
+            } else {
+                index
+            }
+        } else {
+            index - 1
+        };
+        siblings.push(MerkleSibling {
+            hash: layer[sibling_index].clone(),
+            on_right: sibling_index > index,
+        });
+        index /= 2;
+    }
+
+    Some(MerkleProof {
+        leaf_index,
+        siblings,
+    })
+}
+
+/// Verifies that `leaf_hash` is included under `root` according to `proof`,
+/// without needing any of the other transactions.
+pub fn verify_merkle_proof(leaf_hash: &Hash, proof: &MerkleProof, root: &Hash) -> bool {
+    let mut current = leaf_hash.clone();
+    for sibling in &proof.siblings {
+        let mut bytes = if sibling.on_right {
+            current.clone()
+        } else {
+            sibling.hash.clone()
+        };
+        bytes.extend(if sibling.on_right {
+            &sibling.hash
+        } else {
+            &current
+        });
+        current = crypto_hash::digest(crypto_hash::Algorithm::SHA256, &bytes);
+    }
+    &current == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Leaf(u8);
+
+    impl Hashable for Leaf {
+        fn bytes(&self) -> Hash {
+            vec![self.0]
+        }
+    }
+
+    fn leaves(count: u8) -> Vec<Leaf> {
+        (0..count).map(Leaf).collect()
+    }
+
+    #[test]
+    fn proof_verifies_every_leaf_in_odd_sized_trees() {
+        for &count in &[3u8, 5u8] {
+            let items = leaves(count);
+            let root = merkle_root(&items);
+            for index in 0..items.len() {
+                let proof = merkle_proof(&items, index)
+                    .unwrap_or_else(|| panic!("index {} of {} in range", index, count));
+                let leaf_hash = items[index].hash();
+                assert!(
+                    verify_merkle_proof(&leaf_hash, &proof, &root),
+                    "leaf {} of {} failed to verify",
+                    index,
+                    count
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn proof_is_none_out_of_range() {
+        let items = leaves(3);
+        assert!(merkle_proof(&items, 3).is_none());
+    }
+
+    #[test]
+    fn proof_rejects_the_wrong_leaf() {
+        let items = leaves(5);
+        let root = merkle_root(&items);
+        let proof = merkle_proof(&items, 2).unwrap();
+        let wrong_leaf_hash = items[3].hash();
+        assert!(!verify_merkle_proof(&wrong_leaf_hash, &proof, &root));
+    }
+}
@@ -0,0 +1,383 @@
+use crate::block::Block;
+use crate::transaction::{Output, Transaction};
+use crate::Hash;
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Persists a chain's blocks — and a UTXO snapshot alongside them — so they
+/// can be reloaded without replaying every block's mining. `BlockChain`
+/// itself stays storage-agnostic; callers pair it with whichever `Store`
+/// implementation fits their setup (`InMemoryStore` for tests, `FileStore`
+/// for a real node). The snapshot is only a cache: `BlockChain::from_store`
+/// always re-validates every stored block rather than trusting it blindly.
+pub trait Store {
+    type Error;
+
+    fn insert_block(
+        &mut self,
+        block: Block,
+        unspent_outputs: &HashSet<Hash>,
+    ) -> Result<(), Self::Error>;
+    fn block_by_index(&self, index: u32) -> Result<Option<&Block>, Self::Error>;
+    fn block_by_hash(&self, hash: &Hash) -> Result<Option<&Block>, Self::Error>;
+    fn best_block(&self) -> Result<Option<&Block>, Self::Error>;
+    fn unspent_outputs(&self) -> Result<&HashSet<Hash>, Self::Error>;
+    fn height(&self) -> u32;
+}
+
+#[derive(Debug)]
+pub enum InMemoryStoreError {
+    MismatchedIndex,
+}
+
+/// A `Store` backed by an in-process `Vec`. Useful as the default backend
+/// for tests and short-lived nodes; `FileStore` implements the same trait
+/// for a node that needs to survive a restart.
+#[derive(Default)]
+pub struct InMemoryStore {
+    blocks: Vec<Block>,
+    by_hash: HashMap<Hash, usize>,
+    unspent_outputs: HashSet<Hash>,
+}
+
+impl InMemoryStore {
+    pub fn new() -> Self {
+        InMemoryStore::default()
+    }
+}
+
+impl Store for InMemoryStore {
+    type Error = InMemoryStoreError;
+
+    fn insert_block(
+        &mut self,
+        block: Block,
+        unspent_outputs: &HashSet<Hash>,
+    ) -> Result<(), Self::Error> {
+        if block.index != self.blocks.len() as u32 {
+            return Err(InMemoryStoreError::MismatchedIndex);
+        }
+        self.by_hash.insert(block.hash.clone(), self.blocks.len());
+        self.unspent_outputs = unspent_outputs.clone();
+        self.blocks.push(block);
+        Ok(())
+    }
+
+    fn block_by_index(&self, index: u32) -> Result<Option<&Block>, Self::Error> {
+        Ok(self.blocks.get(index as usize))
+    }
+
+    fn block_by_hash(&self, hash: &Hash) -> Result<Option<&Block>, Self::Error> {
+        Ok(self.by_hash.get(hash).and_then(|&i| self.blocks.get(i)))
+    }
+
+    fn best_block(&self) -> Result<Option<&Block>, Self::Error> {
+        Ok(self.blocks.last())
+    }
+
+    fn unspent_outputs(&self) -> Result<&HashSet<Hash>, Self::Error> {
+        Ok(&self.unspent_outputs)
+    }
+
+    fn height(&self) -> u32 {
+        self.blocks.len() as u32
+    }
+}
+
+// --- Manual binary encoding for the append-only block log. -----------------
+//
+// There's no serde dependency in this crate, so `FileStore` rolls its own
+// length-prefixed, big-endian encoding using the same building blocks
+// `Hashable::bytes` already uses elsewhere (`u32`/`u64`/`u128` byte arrays),
+// plus the reverse (`read_*`) direction that nothing else in the crate has
+// needed yet.
+
+fn push_bytes(buffer: &mut Vec<u8>, value: &[u8]) {
+    buffer.extend(&(value.len() as u32).to_be_bytes());
+    buffer.extend(value);
+}
+
+fn read_u32(buffer: &[u8], cursor: &mut usize) -> u32 {
+    let value = u32::from_be_bytes([
+        buffer[*cursor],
+        buffer[*cursor + 1],
+        buffer[*cursor + 2],
+        buffer[*cursor + 3],
+    ]);
+    *cursor += 4;
+    value
+}
+
+fn read_u64(buffer: &[u8], cursor: &mut usize) -> u64 {
+    let mut array = [0u8; 8];
+    array.copy_from_slice(&buffer[*cursor..*cursor + 8]);
+    *cursor += 8;
+    u64::from_be_bytes(array)
+}
+
+fn read_u128(buffer: &[u8], cursor: &mut usize) -> u128 {
+    let mut array = [0u8; 16];
+    array.copy_from_slice(&buffer[*cursor..*cursor + 16]);
+    *cursor += 16;
+    u128::from_be_bytes(array)
+}
+
+fn read_bytes<'a>(buffer: &'a [u8], cursor: &mut usize) -> &'a [u8] {
+    let len = read_u32(buffer, cursor) as usize;
+    let slice = &buffer[*cursor..*cursor + len];
+    *cursor += len;
+    slice
+}
+
+fn encode_output(output: &Output, buffer: &mut Vec<u8>) {
+    push_bytes(buffer, output.to_address.as_bytes());
+    buffer.extend(&output.value.to_be_bytes());
+}
+
+fn decode_output(buffer: &[u8], cursor: &mut usize) -> Output {
+    let to_address =
+        String::from_utf8(read_bytes(buffer, cursor).to_vec()).expect("corrupt block log: address");
+    let value = read_u64(buffer, cursor);
+    Output { to_address, value }
+}
+
+fn encode_outputs(outputs: &[Output], buffer: &mut Vec<u8>) {
+    buffer.extend(&(outputs.len() as u32).to_be_bytes());
+    for output in outputs {
+        encode_output(output, buffer);
+    }
+}
+
+fn decode_outputs(buffer: &[u8], cursor: &mut usize) -> Vec<Output> {
+    let count = read_u32(buffer, cursor);
+    (0..count).map(|_| decode_output(buffer, cursor)).collect()
+}
+
+fn encode_transaction(transaction: &Transaction, buffer: &mut Vec<u8>) {
+    encode_outputs(&transaction.inputs, buffer);
+    encode_outputs(&transaction.outputs, buffer);
+}
+
+fn decode_transaction(buffer: &[u8], cursor: &mut usize) -> Transaction {
+    let inputs = decode_outputs(buffer, cursor);
+    let outputs = decode_outputs(buffer, cursor);
+    Transaction { inputs, outputs }
+}
+
+fn encode_block(block: &Block, buffer: &mut Vec<u8>) {
+    buffer.extend(&block.index.to_be_bytes());
+    buffer.extend(&block.timestamp.to_be_bytes());
+    push_bytes(buffer, &block.hash);
+    push_bytes(buffer, &block.previous_block_hash);
+    buffer.extend(&block.nonce.to_be_bytes());
+    buffer.extend(&(block.transactions.len() as u32).to_be_bytes());
+    for transaction in &block.transactions {
+        encode_transaction(transaction, buffer);
+    }
+    push_bytes(buffer, &block.merkle_root);
+    buffer.extend(&block.difficulty.to_be_bytes());
+    push_bytes(
+        buffer,
+        block.proposer.as_deref().unwrap_or("").as_bytes(),
+    );
+}
+
+fn decode_block(buffer: &[u8], cursor: &mut usize) -> Block {
+    let index = read_u32(buffer, cursor);
+    let timestamp = read_u128(buffer, cursor);
+    let hash = read_bytes(buffer, cursor).to_vec();
+    let previous_block_hash = read_bytes(buffer, cursor).to_vec();
+    let nonce = read_u64(buffer, cursor);
+    let transaction_count = read_u32(buffer, cursor);
+    let transactions = (0..transaction_count)
+        .map(|_| decode_transaction(buffer, cursor))
+        .collect();
+    let merkle_root = read_bytes(buffer, cursor).to_vec();
+    let difficulty = read_u128(buffer, cursor);
+    let proposer_bytes = read_bytes(buffer, cursor);
+    let proposer = if proposer_bytes.is_empty() {
+        None
+    } else {
+        Some(
+            String::from_utf8(proposer_bytes.to_vec())
+                .expect("corrupt block log: proposer"),
+        )
+    };
+
+    Block {
+        index,
+        timestamp,
+        hash,
+        previous_block_hash,
+        nonce,
+        transactions,
+        merkle_root,
+        difficulty,
+        proposer,
+    }
+}
+
+fn encode_unspent_outputs(unspent_outputs: &HashSet<Hash>, buffer: &mut Vec<u8>) {
+    buffer.extend(&(unspent_outputs.len() as u32).to_be_bytes());
+    for hash in unspent_outputs {
+        push_bytes(buffer, hash);
+    }
+}
+
+fn decode_unspent_outputs(buffer: &[u8], cursor: &mut usize) -> HashSet<Hash> {
+    let count = read_u32(buffer, cursor);
+    (0..count)
+        .map(|_| read_bytes(buffer, cursor).to_vec())
+        .collect()
+}
+
+fn encode_entry(block: &Block, unspent_outputs: &HashSet<Hash>) -> Vec<u8> {
+    let mut buffer = vec![];
+    encode_block(block, &mut buffer);
+    encode_unspent_outputs(unspent_outputs, &mut buffer);
+    buffer
+}
+
+fn decode_entry(buffer: &[u8], cursor: &mut usize) -> (Block, HashSet<Hash>) {
+    let block = decode_block(buffer, cursor);
+    let unspent_outputs = decode_unspent_outputs(buffer, cursor);
+    (block, unspent_outputs)
+}
+
+/// A `Store` backed by an append-only log file: each `insert_block` call
+/// appends the encoded block and UTXO snapshot, and `open` replays whatever
+/// is already on disk into an in-memory index so reads don't need to hit
+/// the file.
+pub struct FileStore {
+    path: PathBuf,
+    memory: InMemoryStore,
+}
+
+impl FileStore {
+    /// Opens the block log at `path`, replaying any entries already there.
+    /// A missing file is not created here; the first `insert_block` creates
+    /// it.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut memory = InMemoryStore::new();
+
+        if path.exists() {
+            let mut file = File::open(&path)?;
+            let mut contents = vec![];
+            file.read_to_end(&mut contents)?;
+
+            let mut cursor = 0;
+            while cursor < contents.len() {
+                let (block, unspent_outputs) = decode_entry(&contents, &mut cursor);
+                memory
+                    .insert_block(block, &unspent_outputs)
+                    .expect("corrupt block log: out-of-order index");
+            }
+        }
+
+        Ok(FileStore { path, memory })
+    }
+}
+
+impl Store for FileStore {
+    type Error = io::Error;
+
+    fn insert_block(
+        &mut self,
+        block: Block,
+        unspent_outputs: &HashSet<Hash>,
+    ) -> Result<(), Self::Error> {
+        let entry = encode_entry(&block, unspent_outputs);
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(&entry)?;
+
+        self.memory
+            .insert_block(block, unspent_outputs)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "mismatched block index"))
+    }
+
+    fn block_by_index(&self, index: u32) -> Result<Option<&Block>, Self::Error> {
+        Ok(self.memory.block_by_index(index).unwrap())
+    }
+
+    fn block_by_hash(&self, hash: &Hash) -> Result<Option<&Block>, Self::Error> {
+        Ok(self.memory.block_by_hash(hash).unwrap())
+    }
+
+    fn best_block(&self) -> Result<Option<&Block>, Self::Error> {
+        Ok(self.memory.best_block().unwrap())
+    }
+
+    fn unspent_outputs(&self) -> Result<&HashSet<Hash>, Self::Error> {
+        Ok(self.memory.unspent_outputs().unwrap())
+    }
+
+    fn height(&self) -> u32 {
+        self.memory.height()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(index: u32) -> Block {
+        Block::new(index, index as u128, vec![0; 32], vec![], 1_000)
+    }
+
+    #[test]
+    fn in_memory_store_round_trips_blocks() {
+        let mut store = InMemoryStore::new();
+        let unspent = HashSet::new();
+        store.insert_block(block(0), &unspent).unwrap();
+        store.insert_block(block(1), &unspent).unwrap();
+
+        assert_eq!(store.height(), 2);
+        assert_eq!(store.block_by_index(0).unwrap().unwrap().index, 0);
+        assert_eq!(store.block_by_index(1).unwrap().unwrap().index, 1);
+        assert_eq!(store.best_block().unwrap().unwrap().index, 1);
+    }
+
+    #[test]
+    fn in_memory_store_rejects_out_of_order_blocks() {
+        let mut store = InMemoryStore::new();
+        let unspent = HashSet::new();
+        assert!(matches!(
+            store.insert_block(block(1), &unspent),
+            Err(InMemoryStoreError::MismatchedIndex)
+        ));
+    }
+
+    #[test]
+    fn file_store_persists_and_reloads_blocks() {
+        let path = std::env::temp_dir().join(format!(
+            "rustchain-store-test-{:?}.log",
+            std::thread::current().id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let mut unspent = HashSet::new();
+        unspent.insert(vec![1, 2, 3]);
+
+        {
+            let mut store = FileStore::open(&path).unwrap();
+            store.insert_block(block(0), &unspent).unwrap();
+            store.insert_block(block(1), &HashSet::new()).unwrap();
+        }
+
+        let reopened = FileStore::open(&path).unwrap();
+        assert_eq!(reopened.height(), 2);
+        assert_eq!(reopened.block_by_index(0).unwrap().unwrap().index, 0);
+        assert_eq!(reopened.block_by_index(1).unwrap().unwrap().timestamp, 1);
+        assert!(reopened.unspent_outputs().unwrap().is_empty());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}